@@ -1,75 +1,458 @@
 use crate::project_specification::ProjectSpecification;
 use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+
+const LOCKFILE_NAME: &str = "gdenv.lock";
+
+/// Whether a sync should actually touch the filesystem or just report what it would do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    Apply,
+    DryRun,
+}
+
+/// How `sync_recursive` should treat symlinks found in an addon's source tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymlinkPolicy {
+    /// Recreate the symlink itself at the destination, pointing at the same target.
+    CopySymlinksAsLinks,
+    /// Copy the file/directory the symlink points to, as if it weren't a symlink.
+    Follow,
+    /// Leave symlinks out of the destination entirely.
+    Skip,
+}
+
+impl Default for SymlinkPolicy {
+    fn default() -> Self {
+        SymlinkPolicy::Follow
+    }
+}
+
+/// The set of operations a sync performed (or, in `SyncMode::DryRun`, would perform).
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub created: Vec<PathBuf>,
+    pub updated: Vec<PathBuf>,
+    pub deleted: Vec<PathBuf>,
+    pub skipped: Vec<PathBuf>,
+}
+
+impl SyncReport {
+    fn merge(&mut self, other: SyncReport) {
+        self.created.extend(other.created);
+        self.updated.extend(other.updated);
+        self.deleted.extend(other.deleted);
+        self.skipped.extend(other.skipped);
+    }
+
+    /// Prints a `cargo clean`-style summary of the planned/performed operations.
+    pub fn print_summary(&self) {
+        for path in self.created.iter().chain(&self.updated) {
+            println!("[COPY] {}", path.display());
+        }
+        for path in &self.deleted {
+            println!("[DELETE] {}", path.display());
+        }
+        for path in &self.skipped {
+            println!("[SKIP] {}", path.display());
+        }
+    }
+}
+
+/// Tracks which files each addon wrote on its last sync, so a later run can
+/// tell which of its own files have since disappeared from the source and
+/// are safe to delete from the destination.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncLockfile {
+    #[serde(default)]
+    addon: BTreeMap<String, AddonLock>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AddonLock {
+    /// Relative path -> fingerprint of the source file as of the last sync.
+    #[serde(default)]
+    files: BTreeMap<PathBuf, FileFingerprint>,
+}
+
+/// A source file's size, mtime, and content hash as of the last sync. Size and mtime
+/// are cheap to re-stat on the next run and catch almost every real change, so they're
+/// checked first; the hash is only recomputed when they disagree, and is then compared
+/// with the recorded one so a file whose mtime moved but whose content did not is not
+/// re-copied. A content change that leaves both size and mtime as they were is not
+/// detected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileFingerprint {
+    hash: String,
+    mtime_secs: u64,
+    len: u64,
+}
+
+fn file_fingerprint_metadata(path: &Path) -> Result<(u64, u64)> {
+    let meta = fs::metadata(path).with_context(|| format!("Failed to stat {:?}", path))?;
+    let mtime_secs = meta
+        .modified()
+        .with_context(|| format!("Failed to read mtime of {:?}", path))?
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Ok((meta.len(), mtime_secs))
+}
+
+fn load_lockfile(path: &Path) -> Result<SyncLockfile> {
+    if !path.exists() {
+        return Ok(SyncLockfile::default());
+    }
+    let contents = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    toml::from_str(&contents).with_context(|| format!("Failed to parse {:?}", path))
+}
+
+fn save_lockfile(path: &Path, lockfile: &SyncLockfile) -> Result<()> {
+    let contents = toml::to_string_pretty(lockfile).context("Failed to serialize lockfile")?;
+    fs::write(path, contents).with_context(|| format!("Failed to write {:?}", path))
+}
 
 #[allow(dead_code)]
-fn sync_addons(project_spec: ProjectSpecification, working_dir: &Path) -> Result<()> {
-    for (addon_name, addon_spec) in project_spec.addons {
+fn sync_addons(project_spec: ProjectSpecification, working_dir: &Path, mode: SyncMode) -> Result<SyncReport> {
+    let lockfile_path = working_dir.join(LOCKFILE_NAME);
+    let mut lockfile = load_lockfile(&lockfile_path)?;
+    let dest_base = working_dir.join(&project_spec.project_path);
+
+    let mut report = SyncReport::default();
+    let mut written_by_addon: HashMap<String, BTreeMap<PathBuf, FileFingerprint>> = HashMap::new();
+
+    for (addon_name, addon_spec) in &project_spec.addons {
         let addon_path_str = addon_spec.path.as_deref().unwrap_or(".");
         let source_base = working_dir.join(addon_path_str);
-        let dest_base = working_dir.join(&project_spec.project_path);
 
         if !source_base.exists() {
             tracing::warn!("Addon {} path {:?} does not exist, skipping", addon_name, source_base);
             continue;
         }
 
-        sync_recursive(
+        let includes = addon_spec
+            .include
+            .as_deref()
+            .map(build_gitignore_matcher)
+            .transpose()
+            .with_context(|| format!("Invalid include pattern for addon {}", addon_name))?;
+        let excludes = addon_spec
+            .exclude
+            .as_deref()
+            .map(build_gitignore_matcher)
+            .transpose()
+            .with_context(|| format!("Invalid exclude pattern for addon {}", addon_name))?;
+        let previous_hashes = lockfile.addon.get(addon_name).map(|lock| &lock.files);
+
+        let (written, addon_report) = sync_recursive(
             &source_base,
             &dest_base,
-            addon_spec.include.as_deref(),
-            addon_spec.exclude.as_deref(),
+            includes.as_ref(),
+            excludes.as_ref(),
+            addon_spec.respect_gitignore,
+            previous_hashes,
+            addon_name,
+            mode,
+            addon_spec.symlink_policy,
         )?;
+        report.merge(addon_report);
+        written_by_addon.insert(addon_name.clone(), written);
+    }
+
+    // A path still owned by any addon in this run is never deleted, even if it
+    // used to belong to a different addon whose subtree overlaps this one.
+    let still_owned: BTreeSet<&PathBuf> = written_by_addon.values().flat_map(|files| files.keys()).collect();
+
+    for (addon_name, addon_spec) in &project_spec.addons {
+        if !addon_spec.mirror {
+            continue;
+        }
+        let Some(written) = written_by_addon.get(addon_name) else {
+            continue;
+        };
+        let previous = lockfile.addon.get(addon_name).map(|lock| &lock.files);
+        let Some(previous) = previous else {
+            continue;
+        };
+        for stale in previous.keys().filter(|path| !written.contains_key(path.as_path())) {
+            if still_owned.contains(stale) {
+                continue;
+            }
+            let target = dest_base.join(stale);
+            if !target.exists() {
+                continue;
+            }
+            if mode == SyncMode::Apply {
+                fs::remove_file(&target).with_context(|| format!("Failed to remove stale file {:?}", target))?;
+                prune_empty_ancestors(&target, &dest_base)?;
+            }
+            report.deleted.push(target);
+        }
+    }
+
+    if mode == SyncMode::Apply {
+        for (addon_name, written) in written_by_addon {
+            lockfile.addon.insert(addon_name, AddonLock { files: written });
+        }
+        save_lockfile(&lockfile_path, &lockfile)?;
+    }
+
+    Ok(report)
+}
+
+/// Removes now-empty directories between `path`'s parent and `stop_at`, walking
+/// upward until a non-empty directory (or `stop_at` itself) is reached.
+fn prune_empty_ancestors(path: &Path, stop_at: &Path) -> Result<()> {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == stop_at || !current.starts_with(stop_at) {
+            break;
+        }
+        if fs::read_dir(current).map(|mut entries| entries.next().is_none()).unwrap_or(false) {
+            fs::remove_dir(current).with_context(|| format!("Failed to remove empty directory {:?}", current))?;
+            dir = current.parent();
+        } else {
+            break;
+        }
     }
     Ok(())
 }
 
+/// Compiles an addon's `include`/`exclude` pattern list into a single gitignore-style
+/// matcher, so each addon pays the compilation cost once rather than per file.
+fn build_gitignore_matcher(patterns: &[String]) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new("");
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .with_context(|| format!("Invalid glob pattern {:?}", pattern))?;
+    }
+    builder.build().context("Failed to build gitignore matcher")
+}
+
+/// Copies `source_base` into `dest_base`, applying the include/exclude filters, and
+/// returns the relative file paths that were synced (mapped to their fingerprint, for
+/// mirror tracking and change detection on the next sync) plus a report of what was
+/// created, updated, and skipped.
+/// In `SyncMode::DryRun` the filesystem is never written to, only inspected.
+#[allow(clippy::too_many_arguments)]
 fn sync_recursive(
     source_base: &Path,
     dest_base: &Path,
-    includes: Option<&[PathBuf]>,
-    excludes: Option<&[PathBuf]>,
-) -> Result<()> {
-    // TODO: extend sync_recursive so that it will delete files from the destination
-    //  that don't exist in the source and replace files that exist in both.
-    for entry in WalkDir::new(source_base).into_iter().filter_map(|e| e.ok()) {
+    includes: Option<&Gitignore>,
+    excludes: Option<&Gitignore>,
+    respect_gitignore: bool,
+    previous: Option<&BTreeMap<PathBuf, FileFingerprint>>,
+    addon_name: &str,
+    mode: SyncMode,
+    symlink_policy: SymlinkPolicy,
+) -> Result<(BTreeMap<PathBuf, FileFingerprint>, SyncReport)> {
+    let mut written = BTreeMap::new();
+    let mut report = SyncReport::default();
+    let mut walker_builder = WalkBuilder::new(source_base);
+    walker_builder
+        // Dotfiles (`.DS_Store`, `.idea/`, `.vscode/`, ...) are editor/OS junk, not
+        // addon content, so they're always skipped. The walk drops them before the
+        // include/exclude patterns are checked, so an `include` pattern does not bring
+        // a dotfile back.
+        .hidden(true)
+        .ignore(respect_gitignore)
+        .parents(respect_gitignore)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .follow_links(symlink_policy == SymlinkPolicy::Follow);
+    if respect_gitignore {
+        walker_builder.add_custom_ignore_filename(".gdignore");
+    }
+    let walker = walker_builder.build();
+    for entry in walker.filter_map(|e| e.ok()) {
         let path = entry.path();
         let rel_path = path.strip_prefix(source_base).context("Failed to strip prefix")?;
+        if rel_path.as_os_str().is_empty() {
+            // The source root itself is never filtered, only its contents are.
+            continue;
+        }
+        let is_dir = path.is_dir();
+        let is_symlink = entry.file_type().map(|ft| ft.is_symlink()).unwrap_or(false);
 
-        // 1. Check Excludes: If it matches any exclude pattern, skip it
+        // 1. Check Excludes: excludes always win, regardless of includes
         if let Some(excludes) = excludes {
-            if excludes.iter().any(|ex| rel_path.starts_with(ex)) {
+            if excludes.matched_path_or_any_parents(rel_path, is_dir).is_ignore() {
                 continue;
             }
         }
 
-        // 2. Check Includes: If includes are specified, the path must be inside one of them
+        // 2. Check Includes: if includes are specified, the path must match one of them
         if let Some(includes) = includes {
-            let is_included = includes.iter().any(|inc| {
-                rel_path.starts_with(inc) || inc.starts_with(rel_path)
-            });
-            if !is_included {
+            if !includes.matched_path_or_any_parents(rel_path, is_dir).is_ignore() {
                 continue;
             }
         }
 
-        // 3. Perform Copy
         let target_path = dest_base.join(rel_path);
-        if path.is_dir() {
-            fs::create_dir_all(&target_path)?;
-        } else {
-            if let Some(parent) = target_path.parent() {
-                fs::create_dir_all(parent)?;
+
+        if is_symlink && symlink_policy == SymlinkPolicy::Skip {
+            continue;
+        }
+
+        if is_symlink && symlink_policy == SymlinkPolicy::CopySymlinksAsLinks {
+            let link_target = fs::read_link(path).with_context(|| format!("Failed to read symlink {:?}", path))?;
+            let prev = previous.and_then(|fingerprints| fingerprints.get(rel_path));
+            let new_hash = symlink_hash(&link_target);
+            let target_existed = target_path.symlink_metadata().is_ok();
+            let unchanged = target_existed && prev.is_some_and(|fp| fp.hash == new_hash);
+            if !unchanged {
+                if mode == SyncMode::Apply {
+                    recreate_symlink(path, &link_target, &target_path)?;
+                }
+                if target_existed {
+                    report.updated.push(target_path);
+                } else {
+                    report.created.push(target_path);
+                }
+            } else {
+                report.skipped.push(target_path);
+            }
+            // A symlink's own size/mtime aren't meaningful here (we only care about
+            // the text of the link target), so the fingerprint's hash is what matters;
+            // size/mtime are left at 0 and simply never consulted for symlinks.
+            written.insert(rel_path.to_path_buf(), FileFingerprint { hash: new_hash, mtime_secs: 0, len: 0 });
+            continue;
+        }
+
+        // A directory that itself passes the filters is only materialized here if
+        // it's genuinely empty on the source side. A non-empty directory whose
+        // contents are entirely excluded (e.g. `.git` with `exclude = ["**/.git/**"]`)
+        // is left uncreated; any directory that does contain a surviving file gets
+        // created lazily as a side effect of that file's copy below.
+        if is_dir {
+            if mode == SyncMode::Apply {
+                let is_empty = fs::read_dir(path).map(|mut entries| entries.next().is_none()).unwrap_or(false);
+                if is_empty {
+                    fs::create_dir_all(&target_path)?;
+                }
+            }
+            continue;
+        }
+
+        let prev = previous.and_then(|fingerprints| fingerprints.get(rel_path));
+        let (should_copy, fingerprint) = needs_copy(path, &target_path, prev)?;
+        let target_existed = target_path.exists();
+        if should_copy {
+            if mode == SyncMode::Apply {
+                if let Some(parent) = target_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(path, &target_path)
+                    .with_context(|| format!("Failed to copy {:?} to {:?}", path, target_path))?;
             }
-            fs::copy(path, &target_path)
-                .with_context(|| format!("Failed to copy {:?} to {:?}", path, target_path))?;
+            if target_existed {
+                report.updated.push(target_path);
+            } else {
+                report.created.push(target_path);
+            }
+        } else {
+            report.skipped.push(target_path);
         }
+        written.insert(rel_path.to_path_buf(), fingerprint);
     }
+    tracing::info!(
+        "Addon {}: {} copied, {} skipped unchanged",
+        addon_name,
+        report.created.len() + report.updated.len(),
+        report.skipped.len()
+    );
+    Ok((written, report))
+}
+
+fn symlink_hash(link_target: &Path) -> String {
+    blake3::hash(link_target.to_string_lossy().as_bytes()).to_hex().to_string()
+}
+
+/// Recreates `source`'s symlink at `target`, pointing at the same (possibly relative) path.
+#[cfg_attr(not(windows), allow(unused_variables))]
+fn recreate_symlink(source: &Path, link_target: &Path, target: &Path) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if target.symlink_metadata().is_ok() {
+        if target.is_dir() && !target.is_symlink() {
+            fs::remove_dir_all(target).with_context(|| format!("Failed to remove {:?}", target))?;
+        } else {
+            fs::remove_file(target).with_context(|| format!("Failed to remove {:?}", target))?;
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(link_target, target)
+            .with_context(|| format!("Failed to create symlink {:?} -> {:?}", target, link_target))?;
+    }
+    #[cfg(windows)]
+    {
+        let points_to_dir = fs::metadata(source).map(|m| m.is_dir()).unwrap_or(false);
+        if points_to_dir {
+            std::os::windows::fs::symlink_dir(link_target, target)
+        } else {
+            std::os::windows::fs::symlink_file(link_target, target)
+        }
+        .with_context(|| format!("Failed to create symlink {:?} -> {:?}", target, link_target))?;
+    }
+
     Ok(())
 }
 
+/// Decides whether `source` needs to be (re)copied to `target`, and returns the
+/// fingerprint to record for `source` either way.
+///
+/// Mirrors Cargo's dep-info freshness check: if `source`'s size and mtime match what
+/// was recorded last sync, it's trusted as unchanged without reading a single byte of
+/// either file. The destination is only re-stat'd (not re-hashed) as a cheap self-heal
+/// for the common corruption case of a file being deleted or truncated out-of-band; a
+/// destination that was edited to a same-size file in place is not caught by this fast
+/// path. Source content is only actually hashed when its size or mtime moved, or when
+/// there's no prior record (first sync) to trust.
+fn needs_copy(source: &Path, target: &Path, prev: Option<&FileFingerprint>) -> Result<(bool, FileFingerprint)> {
+    let (source_len, source_mtime_secs) = file_fingerprint_metadata(source)?;
+
+    if let Some(prev) = prev {
+        if prev.len == source_len && prev.mtime_secs == source_mtime_secs {
+            let target_fresh = fs::metadata(target).map(|meta| meta.len() == source_len).unwrap_or(false);
+            let fingerprint = FileFingerprint { hash: prev.hash.clone(), len: source_len, mtime_secs: source_mtime_secs };
+            return Ok((!target_fresh, fingerprint));
+        }
+    }
+
+    let source_hash = hash_file(source)?;
+    let fingerprint = FileFingerprint { hash: source_hash.clone(), len: source_len, mtime_secs: source_mtime_secs };
+
+    let should_copy = if !target.exists() {
+        true
+    } else {
+        let target_len = fs::metadata(target).with_context(|| format!("Failed to stat {:?}", target))?.len();
+        if target_len != source_len {
+            true
+        } else if let Some(prev) = prev {
+            prev.hash != source_hash
+        } else {
+            hash_file(target)? != source_hash
+        }
+    };
+    Ok((should_copy, fingerprint))
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    Ok(blake3::hash(&bytes).to_hex().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,7 +499,7 @@ include = ["addons"]
         fs::write(version_file, str_spec)?;
         let project_spec = load_godot_project_spec(tmp_dir.path())?;
 
-        sync_addons(project_spec, tmp_dir.path())?;
+        sync_addons(project_spec, tmp_dir.path(), SyncMode::Apply)?;
 
         assert!(tmp_dir.path().join("addons/test-addon1/plugin.cfg").exists());
         assert!(tmp_dir.path().join("addons/test-addon2/plugin.cfg").exists());
@@ -124,4 +507,109 @@ include = ["addons"]
 
         Ok(())
     }
+
+    #[test]
+    fn test_mirror_sync_deletes_stale_files() -> Result<()> {
+        let tmp_dir = TempDir::new("gdenv-mirror-test")?;
+        let addon_source = tmp_dir.path().join("addon-src");
+        fs::create_dir_all(addon_source.join("addons/my-addon"))?;
+        fs::write(addon_source.join("addons/my-addon/plugin.cfg"), "a")?;
+        fs::write(addon_source.join("addons/my-addon/old.gd"), "b")?;
+
+        let version_file = tmp_dir.path().join("gdenv.toml");
+        let str_spec = format!(
+            r#"
+[godot]
+version = "4.6.0-stable"
+
+[addon.my-addon]
+path = "{}"
+mirror = true
+        "#,
+            addon_source.display(),
+        );
+        fs::write(&version_file, &str_spec)?;
+
+        let project_spec = load_godot_project_spec(tmp_dir.path())?;
+        sync_addons(project_spec, tmp_dir.path(), SyncMode::Apply)?;
+
+        let dest_old = tmp_dir.path().join("addons/my-addon/old.gd");
+        assert!(dest_old.exists());
+
+        fs::remove_file(addon_source.join("addons/my-addon/old.gd"))?;
+
+        let project_spec = load_godot_project_spec(tmp_dir.path())?;
+        sync_addons(project_spec, tmp_dir.path(), SyncMode::Apply)?;
+
+        assert!(!dest_old.exists());
+        assert!(tmp_dir.path().join("addons/my-addon/plugin.cfg").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run_reports_without_touching_disk() -> Result<()> {
+        let tmp_dir = TempDir::new("gdenv-dry-run-test")?;
+        let addon_source = tmp_dir.path().join("addon-src");
+        fs::create_dir_all(addon_source.join("addons/my-addon"))?;
+        fs::write(addon_source.join("addons/my-addon/plugin.cfg"), "a")?;
+
+        let version_file = tmp_dir.path().join("gdenv.toml");
+        let str_spec = format!(
+            r#"
+[godot]
+version = "4.6.0-stable"
+
+[addon.my-addon]
+path = "{}"
+        "#,
+            addon_source.display(),
+        );
+        fs::write(&version_file, &str_spec)?;
+
+        let project_spec = load_godot_project_spec(tmp_dir.path())?;
+        let report = sync_addons(project_spec, tmp_dir.path(), SyncMode::DryRun)?;
+
+        let dest_plugin_cfg = tmp_dir.path().join("addons/my-addon/plugin.cfg");
+        assert!(!dest_plugin_cfg.exists());
+        assert!(!tmp_dir.path().join(LOCKFILE_NAME).exists());
+        assert_eq!(report.created, vec![dest_plugin_cfg]);
+        assert!(report.updated.is_empty());
+        assert!(report.deleted.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_symlink_policy_copy_as_link() -> Result<()> {
+        let tmp_dir = TempDir::new("gdenv-symlink-test")?;
+        let addon_source = tmp_dir.path().join("addon-src");
+        fs::create_dir_all(addon_source.join("addons/my-addon"))?;
+        fs::write(addon_source.join("addons/my-addon/real.gd"), "a")?;
+        std::os::unix::fs::symlink("real.gd", addon_source.join("addons/my-addon/linked.gd"))?;
+
+        let version_file = tmp_dir.path().join("gdenv.toml");
+        let str_spec = format!(
+            r#"
+[godot]
+version = "4.6.0-stable"
+
+[addon.my-addon]
+path = "{}"
+symlink_policy = "copy_symlinks_as_links"
+        "#,
+            addon_source.display(),
+        );
+        fs::write(&version_file, &str_spec)?;
+
+        let project_spec = load_godot_project_spec(tmp_dir.path())?;
+        sync_addons(project_spec, tmp_dir.path(), SyncMode::Apply)?;
+
+        let dest_link = tmp_dir.path().join("addons/my-addon/linked.gd");
+        assert!(dest_link.is_symlink());
+        assert_eq!(fs::read_link(&dest_link)?, PathBuf::from("real.gd"));
+
+        Ok(())
+    }
 }